@@ -103,6 +103,52 @@
 //!
 //! Finally, `Planet::iter()` gives a `DoubleEndedIterator` over all variants in declaration order, and `Planet::COUNT` is
 //! the total number of variants.
+//!
+//! ## `EnumMap` companion type
+//!
+//! Alongside the enum itself, the macro emits a `#[enum_name]Map<T>` struct (e.g. `PlanetMap<T>`) wrapping a dense
+//! `[T; COUNT]` array, one slot per variant. It can be built from a closure with `PlanetMap::from_fn(|planet| ...)`,
+//! indexed directly with `map[Planet::Earth]` (via `Index`/`IndexMut`), or accessed through `get`/`get_mut`, and
+//! iterated with `iter`/`iter_mut`, which yield `(Planet, &T)`/`(Planet, &mut T)` pairs in declaration order.
+//!
+//! ## Reverse lookups with `#[key]`
+//!
+//! Marking a field of the layout struct with `#[key]` generates a `from_<field>(value: &Ty) -> Option<Self>`
+//! associated function that finds the variant whose field matches `value`. For `&'static str` fields whose values
+//! are all string literals, this compiles down to a `match` on the string (a compile error if two variants share
+//! the same value); any other field type falls back to a linear scan via `Self::iter()` and the existing `Deref`
+//! impl.
+//!
+//! ## Sparse discriminants with `#[const_table(id = ..)]`
+//!
+//! By default, variants are numbered linearly from 0. Tagging every data variant with
+//! `#[const_table(id = 10)]` instead gives the enum those exact, possibly non-contiguous discriminants
+//! (handy for modelling an externally-defined ID space), while `iter()`, `Deref` and `TryFrom` keep
+//! working exactly as before -- without a cast or `transmute`: `Deref` finds the table slot by matching
+//! on the variant, `TryFrom` matches the integer against the ids, and `iter()` yields a fixed list of the
+//! variants. Either
+//! all data variants need an explicit id, or none do; mixing the two is a compile error, as are
+//! duplicate ids.
+//!
+//! ## `VARIANTS`, `name()` and `FromStr`
+//!
+//! The macro also emits a `pub const VARIANTS: [&'static str; COUNT]` holding each variant's name in
+//! declaration order, a `pub const fn name(self) -> &'static str` that looks itself up in `VARIANTS`, and
+//! a `core::str::FromStr` impl that parses a name back into the matching variant, failing with the
+//! generated `#[enum_name]ParseError` marker type otherwise. By default the emitted name is the variant's
+//! identifier; tag a variant with `#[const_table(rename = "...")]` to use a different string instead.
+//!
+//! ## `From<Planet> for #repr_type`
+//!
+//! Alongside `TryFrom<#repr_type> for Planet`, the macro emits the infallible reverse conversion
+//! `impl From<Planet> for #repr_type`, so `let id: u32 = species.into();` works without an `as` cast.
+//!
+//! ## A `const fn` accessor alongside `Deref`
+//!
+//! `Deref` isn't usable in `const`/`static` contexts, so the macro also emits
+//! `pub const fn info(self) -> &'static PlanetInfo`, routed through the same generated table, letting you
+//! write `const G: f32 = Planet::Earth.info().mass;`. The accessor's name defaults to `info`, and can be
+//! overridden with `#[const_table(accessor = "...")]` on the enum itself.
 
 extern crate quote;
 extern crate syn;
@@ -110,16 +156,64 @@ extern crate syn;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::parse::Error;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, Expr, Ident, ItemEnum, ItemStruct, Variant};
+use syn::{parse_macro_input, Attribute, Expr, Ident, ItemEnum, ItemStruct, Variant};
+
+/// Parses the macro's own `#[const_table(key = value, ..)]` configuration attribute off a variant,
+/// removing it from `attrs` so it doesn't leak into the emitted code, and returns its `key = value`
+/// pairs for the caller to interpret.
+fn take_const_table_meta(attrs: &mut Vec<Attribute>) -> Vec<(Ident, syn::Lit)> {
+    let mut meta = Vec::new();
+
+    attrs.retain(|attr| {
+        if !attr.path.is_ident("const_table") {
+            return true;
+        }
+
+        if let Ok(syn::Meta::List(list)) = attr.parse_meta() {
+            for nested in list.nested {
+                if let syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) = nested {
+                    if let Some(ident) = nv.path.get_ident() {
+                        meta.push((ident.clone(), nv.lit));
+                    }
+                }
+            }
+        }
+
+        false
+    });
+
+    meta
+}
 
 #[proc_macro_attribute]
-pub fn const_table(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn const_table(attr: TokenStream, item: TokenStream) -> TokenStream {
     let mut errors = proc_macro2::TokenStream::new();
 
+    let attr_args = parse_macro_input!(attr as syn::AttributeArgs);
+    let mut accessor_name = None;
+    for nested in attr_args {
+        if let syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) = nested {
+            if nv.path.is_ident("accessor") {
+                if let syn::Lit::Str(s) = nv.lit {
+                    accessor_name = Some(Ident::new(&s.value(), s.span()));
+                } else {
+                    let span = nv.lit.span();
+                    let message = "const_table(accessor = ..) expects a string literal";
+                    errors.extend(Error::new(span, message).to_compile_error());
+                }
+            } else {
+                let span = nv.path.span();
+                let message = "unknown const_table attribute, expected `accessor`";
+                errors.extend(Error::new(span, message).to_compile_error());
+            }
+        }
+    }
+    let accessor_name = accessor_name.unwrap_or_else(|| Ident::new("info", Span::call_site()));
+
     let input_item = parse_macro_input!(item as syn::Item);
     let input_item = if let syn::Item::Enum(e) = input_item {
         e
@@ -181,24 +275,59 @@ pub fn const_table(_attr: TokenStream, item: TokenStream) -> TokenStream {
         (attrs, repr.unwrap_or_else(|| Ident::new("u32", Span::call_site())))
     };
 
+    let brace_span = input_item.brace_token.span;
+
     let mut input_variants = input_item.variants.iter();
     let first_variant = input_variants.next();
 
-    let (variants, value_exprs): (Punctuated<Variant, syn::token::Comma>, Vec<Expr>) = input_variants.map(|variant| {
+    // Each data variant may carry `#[const_table(id = .., rename = "..")]` tags: `id` gives the variant
+    // an explicit, possibly sparse discriminant; `rename` overrides the name emitted into `VARIANTS`/
+    // `name()`/`FromStr`, which otherwise default to the variant identifier.
+    let processed: Vec<(Variant, Expr, Option<syn::LitInt>, Option<syn::LitStr>)> = input_variants.map(|variant| {
         if !variant.fields.is_empty() {
             let span = variant.fields.span();
             let message = "in a const_table enum, only the first variant should have fields";
             errors.extend(Error::new(span, message).to_compile_error());
         }
 
-        if let Some((_, expr)) = &variant.discriminant {
+        let mut variant = variant.clone();
+        let mut explicit_id = None;
+        let mut rename = None;
+        for (key, lit) in take_const_table_meta(&mut variant.attrs) {
+            if key == "id" {
+                if let syn::Lit::Int(lit_int) = lit {
+                    explicit_id = Some(lit_int);
+                } else {
+                    let span = lit.span();
+                    let message = "const_table(id = ..) expects an integer literal";
+                    errors.extend(Error::new(span, message).to_compile_error());
+                }
+            } else if key == "rename" {
+                if let syn::Lit::Str(lit_str) = lit {
+                    rename = Some(lit_str);
+                } else {
+                    let span = lit.span();
+                    let message = "const_table(rename = ..) expects a string literal";
+                    errors.extend(Error::new(span, message).to_compile_error());
+                }
+            } else {
+                let span = key.span();
+                let message = format!("unknown const_table variant attribute `{}`", key);
+                errors.extend(Error::new(span, message).to_compile_error());
+            }
+        }
+
+        if let Some((_, expr)) = variant.discriminant.clone() {
             let v = Variant {
-                discriminant: None,
+                discriminant: explicit_id.clone().map(|lit_int| {
+                    let eq_token = syn::token::Eq { spans: [lit_int.span()] };
+                    (eq_token, Expr::Lit(syn::ExprLit { attrs: Vec::new(), lit: syn::Lit::Int(lit_int) }))
+                }),
                 fields: syn::Fields::Unit,
-                ..(*variant).clone()
+                ..variant
             };
 
-            (v, expr.clone())
+            (v, expr, explicit_id, rename)
         } else {
             let span = variant.span();
             let message = "in a const_table enum, all but the first variant should have a discriminant expression";
@@ -208,18 +337,41 @@ pub fn const_table(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 attrs: Vec::new(), paren_token: syn::token::Paren { span: variant.ident.span() }, elems: Punctuated::new()
             });
 
-            (variant.clone(), empty_expr)
+            (variant, empty_expr, explicit_id, rename)
         }
-    }).unzip();
+    }).collect();
+
+    let variants: Punctuated<Variant, syn::token::Comma> = processed.iter().map(|(v, ..)| v.clone()).collect();
+    let value_exprs: Vec<Expr> = processed.iter().map(|(_, expr, ..)| expr.clone()).collect();
+    let explicit_ids: Vec<Option<syn::LitInt>> = processed.iter().map(|(_, _, id, _)| id.clone()).collect();
+    let renames: Vec<Option<syn::LitStr>> = processed.into_iter().map(|(_, _, _, rename)| rename).collect();
 
     if variants.is_empty() {
-        let span = input_item.brace_token.span;
+        let span = brace_span;
         let message = "a const_table enum needs at least one variant with a discriminant expression";
         errors.extend(Error::new(span, message).to_compile_error());
         return errors.into();
     }
 
-    let struct_decl = if let Some(v) = first_variant {
+    let any_explicit_id = explicit_ids.iter().any(Option::is_some);
+    let sparse_discriminants = explicit_ids.iter().all(Option::is_some);
+
+    if any_explicit_id && !sparse_discriminants {
+        let message = "either give every variant of a const_table enum an explicit #[const_table(id = ..)], or none";
+        errors.extend(Error::new(brace_span, message).to_compile_error());
+    } else if sparse_discriminants {
+        let mut seen = std::collections::HashSet::new();
+        for id in explicit_ids.iter().flatten() {
+            if let Ok(value) = id.base10_parse::<i128>() {
+                if !seen.insert(value) {
+                    let message = format!("duplicate #[const_table(id = {})] in this enum", value);
+                    errors.extend(Error::new(id.span(), message).to_compile_error());
+                }
+            }
+        }
+    }
+
+    let mut struct_decl = if let Some(v) = first_variant {
         use syn::Fields::Named;
         if let Named(fields) = &v.fields {
             ItemStruct {
@@ -247,13 +399,193 @@ pub fn const_table(_attr: TokenStream, item: TokenStream) -> TokenStream {
     };
     let struct_name = &struct_decl.ident;
 
+    // `#[key]` marks a field of the layout struct as eligible for reverse lookup; strip it here so
+    // it doesn't leak into the emitted struct, where it would be an unrecognized attribute.
+    let keyed_fields: Vec<(Ident, syn::Type)> = if let syn::Fields::Named(fields) = &mut struct_decl.fields {
+        fields
+            .named
+            .iter_mut()
+            .filter_map(|field| {
+                let mut is_key = false;
+                field.attrs.retain(|attr| {
+                    if attr.path.is_ident("key") {
+                        is_key = true;
+                        false
+                    } else {
+                        true
+                    }
+                });
+
+                if is_key {
+                    Some((field.ident.clone().unwrap(), field.ty.clone()))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
     let table_size = variants.len();
+    let variant_idents: Vec<Ident> = variants.iter().map(|v| v.ident.clone()).collect();
+    let variant_names: Vec<syn::LitStr> = variant_idents.iter().zip(renames).map(|(ident, rename)| {
+        rename.unwrap_or_else(|| syn::LitStr::new(&ident.to_string(), ident.span()))
+    }).collect();
     let enum_decl = ItemEnum {
         attrs: enum_attrs,
         variants,
         ..input_item
     };
     let enum_name = &enum_decl.ident;
+    let map_name = format_ident!("{}Map", enum_name);
+    let parse_error_name = format_ident!("{}ParseError", enum_name);
+
+    let key_fns = {
+        fn struct_field_expr<'a>(expr: &'a Expr, field_ident: &Ident) -> Option<&'a Expr> {
+            if let Expr::Struct(struct_expr) = expr {
+                struct_expr.fields.iter().find_map(|field_value| match &field_value.member {
+                    syn::Member::Named(name) if name == field_ident => Some(&field_value.expr),
+                    _ => None,
+                })
+            } else {
+                None
+            }
+        }
+
+        fn as_str_lit(expr: &Expr) -> Option<&syn::LitStr> {
+            match expr {
+                Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit_str), .. }) => Some(lit_str),
+                _ => None,
+            }
+        }
+
+        fn is_static_str(ty: &syn::Type) -> bool {
+            match ty {
+                syn::Type::Reference(r) => {
+                    r.lifetime.as_ref().is_some_and(|lt| lt.ident == "static")
+                        && matches!(&*r.elem, syn::Type::Path(p) if p.path.is_ident("str"))
+                }
+                _ => false,
+            }
+        }
+
+        let mut key_fns = proc_macro2::TokenStream::new();
+        for (field_ident, field_ty) in &keyed_fields {
+            let fn_name = format_ident!("from_{}", field_ident);
+
+            let literals: Option<Vec<&syn::LitStr>> = if is_static_str(field_ty) {
+                value_exprs
+                    .iter()
+                    .map(|expr| struct_field_expr(expr, field_ident).and_then(as_str_lit))
+                    .collect()
+            } else {
+                None
+            };
+
+            if let Some(literals) = literals {
+                let mut seen = std::collections::HashSet::new();
+                let mut duplicate = None;
+                for lit in &literals {
+                    if !seen.insert(lit.value()) {
+                        let message = format!(
+                            "duplicate value {:?} for #[key] field `{}`; from_{} requires unique keys to build a match",
+                            lit.value(), field_ident, field_ident
+                        );
+                        duplicate = Some(Error::new(lit.span(), message));
+                        break;
+                    }
+                }
+
+                if let Some(error) = duplicate {
+                    errors.extend(error.to_compile_error());
+                    continue;
+                }
+
+                key_fns.extend(quote! {
+                    pub fn #fn_name(value: &#field_ty) -> Option<Self> {
+                        use #enum_name::*;
+                        match *value {
+                            #( #literals => Some(#variant_idents), )*
+                            _ => None,
+                        }
+                    }
+                });
+            } else {
+                key_fns.extend(quote! {
+                    pub fn #fn_name(value: &#field_ty) -> Option<Self> {
+                        Self::iter().find(|variant| variant.#field_ident == *value)
+                    }
+                });
+            }
+        }
+        key_fns
+    };
+
+    // With linear discriminants, a variant's own repr value already is its position in `TABLE`. With
+    // explicit `#[const_table(id = ..)]` tags, the real discriminants are the external ids instead, so
+    // the table position has to be recovered by matching on the variant identity.
+    let table_index_body = if sparse_discriminants {
+        let indices: Vec<usize> = (0..table_size).collect();
+        quote! {
+            match self {
+                #( #enum_name::#variant_idents => #indices, )*
+            }
+        }
+    } else {
+        quote! { self as usize }
+    };
+
+    let discriminants_decl = if sparse_discriminants {
+        let ids: Vec<syn::LitInt> = explicit_ids.iter().map(|id| id.clone().unwrap()).collect();
+        quote! {
+            pub const DISCRIMINANTS: [#repr_type; #table_size] = [ #(#ids),* ];
+        }
+    } else {
+        quote! {}
+    };
+
+    let iter_body = if sparse_discriminants {
+        quote! {
+            [ #(#enum_name::#variant_idents),* ].iter().copied()
+        }
+    } else {
+        quote! {
+            // transmuting here is fine because... (see try_from)
+            (0..Self::COUNT).map(|i| unsafe { core::mem::transmute(i as #repr_type) })
+        }
+    };
+
+    let try_from_impl = if sparse_discriminants {
+        let ids: Vec<syn::LitInt> = explicit_ids.iter().map(|id| id.clone().unwrap()).collect();
+        quote! {
+            impl core::convert::TryFrom<#repr_type> for #enum_name {
+                type Error = #repr_type;
+                fn try_from(i: #repr_type) -> core::result::Result<Self, #repr_type> {
+                    use #enum_name::*;
+                    match i {
+                        #( #ids => core::result::Result::Ok(#variant_idents), )*
+                        _ => core::result::Result::Err(i),
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl core::convert::TryFrom<#repr_type> for #enum_name {
+                type Error = #repr_type;
+                fn try_from(i: #repr_type) -> core::result::Result<Self, #repr_type> {
+                    if (i as usize) < Self::COUNT {
+                        // transmuting here is fine because all values in range are valid, since
+                        // discriminants are assigned linearly starting at 0.
+                        core::result::Result::Ok(unsafe { core::mem::transmute(i) })
+                    } else {
+                        core::result::Result::Err(i)
+                    }
+                }
+            }
+        }
+    };
 
     let expanded = quote! {
         #errors
@@ -266,32 +598,105 @@ pub fn const_table(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
         impl #enum_name {
             pub const COUNT: usize = #table_size;
+            #discriminants_decl
+            pub const VARIANTS: [&'static str; #table_size] = [ #(#variant_names),* ];
+
+            #[doc(hidden)]
+            const TABLE: [#struct_name; #table_size] = {
+                use #enum_name::*;
+                [ #(#value_exprs),* ]
+            };
+
             pub fn iter() -> impl core::iter::DoubleEndedIterator<Item = Self> {
-                // transmuting here is fine because... (see try_from)
-                (0..Self::COUNT).map(|i| unsafe { core::mem::transmute(i as #repr_type) })
+                #iter_body
+            }
+
+            pub const fn name(self) -> &'static str {
+                Self::VARIANTS[self.__table_index()]
+            }
+
+            /// `const fn` equivalent of the `Deref` impl, usable in `const`/`static` contexts.
+            pub const fn #accessor_name(self) -> &'static #struct_name {
+                &Self::TABLE[self.__table_index()]
+            }
+
+            #[doc(hidden)]
+            const fn __table_index(self) -> usize {
+                #table_index_body
             }
+
+            #key_fns
         }
 
         impl core::ops::Deref for #enum_name {
             type Target = #struct_name;
             fn deref(&self) -> &Self::Target {
+                &Self::TABLE[self.__table_index()]
+            }
+        }
+
+        #try_from_impl
+
+        impl core::convert::From<#enum_name> for #repr_type {
+            fn from(value: #enum_name) -> #repr_type {
+                value as #repr_type
+            }
+        }
+
+        #[derive(core::marker::Copy, core::clone::Clone, core::fmt::Debug, core::cmp::PartialEq, core::cmp::Eq)]
+        pub struct #parse_error_name;
+
+        impl core::str::FromStr for #enum_name {
+            type Err = #parse_error_name;
+            fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
                 use #enum_name::*;
-                const TABLE: [#struct_name; #table_size] = [ #(#value_exprs),* ];
-                &TABLE[*self as usize]
+                match s {
+                    #( #variant_names => core::result::Result::Ok(#variant_idents), )*
+                    _ => core::result::Result::Err(#parse_error_name),
+                }
             }
         }
 
-        impl core::convert::TryFrom<#repr_type> for #enum_name {
-            type Error = #repr_type;
-            fn try_from(i: #repr_type) -> core::result::Result<Self, #repr_type> {
-                if (i as usize) < Self::COUNT {
-                    // transmuting here is fine because all values in range are valid, since
-                    // discriminants are assigned linearly starting at 0.
-                    core::result::Result::Ok(unsafe { core::mem::transmute(i) })
-                } else {
-                    core::result::Result::Err(i)
+        #[derive(core::clone::Clone)]
+        pub struct #map_name<T> {
+            values: [T; #table_size],
+        }
+
+        impl<T> #map_name<T> {
+            pub fn from_fn<F: core::ops::Fn(#enum_name) -> T>(f: F) -> Self {
+                Self {
+                    values: [ #( f(#enum_name::#variant_idents) ),* ],
                 }
             }
+
+            pub fn get(&self, index: #enum_name) -> &T {
+                &self.values[index.__table_index()]
+            }
+
+            pub fn get_mut(&mut self, index: #enum_name) -> &mut T {
+                &mut self.values[index.__table_index()]
+            }
+
+            pub fn iter(&self) -> impl core::iter::Iterator<Item = (#enum_name, &T)> {
+                #enum_name::iter().zip(self.values.iter())
+            }
+
+            pub fn iter_mut(&mut self) -> impl core::iter::Iterator<Item = (#enum_name, &mut T)> {
+                #enum_name::iter().zip(self.values.iter_mut())
+            }
+        }
+
+        impl<T> core::ops::Index<#enum_name> for #map_name<T> {
+            type Output = T;
+            fn index(&self, index: #enum_name) -> &T {
+                self.get(index)
+            }
+        }
+
+        impl<T> core::ops::IndexMut<#enum_name> for #map_name<T> {
+            fn index_mut(&mut self, index: #enum_name) -> &mut T {
+                self.get_mut(index)
+            }
         }
     };
     expanded.into()
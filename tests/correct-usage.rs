@@ -7,9 +7,12 @@ pub enum SpeciesID {
     /// Bundle of all relevant attributes of a species.
     #[derive(Debug)]
     SpeciesInfo {
+        #[key]
         pub sound: &'static str,
+        #[key]
         pub legs: u64,
     },
+    #[const_table(rename = "kitty")]
     Cat = SpeciesInfo {
         sound: "Meow!",
         legs: 4,
@@ -24,6 +27,19 @@ pub enum SpeciesID {
     },
 }
 
+/// A sparse, externally-defined ID space, e.g. assigned by a network protocol.
+#[const_table(accessor = "meta")]
+pub enum PacketKind {
+    PacketInfo { pub name: &'static str },
+
+    #[const_table(id = 1)]
+    Ping = PacketInfo { name: "Ping" },
+    #[const_table(id = 2)]
+    Pong = PacketInfo { name: "Pong" },
+    #[const_table(id = 16)]
+    Data = PacketInfo { name: "Data" },
+}
+
 fn main() {
     use SpeciesID::*;
 
@@ -42,6 +58,66 @@ fn main() {
     assert_eq!(Ok(Human), SpeciesID::try_from(2));
     assert_eq!(Err(3), SpeciesID::try_from(3));
 
+    let id: u32 = Dog.into();
+    assert_eq!(id, 1);
+
     assert_eq!(format!("{}", Human.sound), "Hello, World");
     assert_eq!(format!("{:?}", *Cat), "SpeciesInfo { sound: \"Meow!\", legs: 4 }");
+
+    let mut leg_count = SpeciesIDMap::from_fn(|species| species.legs);
+    assert_eq!(leg_count[Cat], 4);
+    assert_eq!(leg_count[Human], 2);
+
+    *leg_count.get_mut(Human) = 3;
+    assert_eq!(
+        leg_count.iter().collect::<Vec<_>>(),
+        [(Cat, &4), (Dog, &4), (Human, &3)]
+    );
+
+    for (species, legs) in leg_count.iter_mut() {
+        *legs += species.legs;
+    }
+    assert_eq!(*leg_count.get(Cat), 8);
+
+    assert_eq!(SpeciesID::from_sound(&"Woof!"), Some(Dog));
+    assert_eq!(SpeciesID::from_sound(&"Quack!"), None);
+
+    // `legs` is not a `&'static str`, so this goes through the linear-scan fallback.
+    assert_eq!(SpeciesID::from_legs(&4), Some(Cat));
+    assert_eq!(SpeciesID::from_legs(&100), None);
+
+    const CAT_LEGS: u64 = Cat.info().legs;
+    assert_eq!(CAT_LEGS, 4);
+    assert_eq!(Human.info().sound, "Hello, World");
+
+    assert_eq!(SpeciesID::VARIANTS, ["kitty", "Dog", "Human"]);
+    assert_eq!(Cat.name(), "kitty");
+    assert_eq!(Dog.name(), "Dog");
+    assert_eq!("kitty".parse(), Ok(Cat));
+    assert_eq!("Human".parse(), Ok(Human));
+    assert_eq!("Woof!".parse::<SpeciesID>(), Err(SpeciesIDParseError));
+
+    use PacketKind::*;
+
+    assert_eq!(PacketKind::DISCRIMINANTS, [1, 2, 16]);
+    assert_eq!(Ping as u32, 1);
+    assert_eq!(Data as u32, 16);
+
+    assert_eq!(
+        PacketKind::iter().collect::<Vec<PacketKind>>(),
+        [Ping, Pong, Data]
+    );
+
+    assert_eq!(Ok(Ping), PacketKind::try_from(1));
+    assert_eq!(Ok(Data), PacketKind::try_from(16));
+    assert_eq!(Err(3), PacketKind::try_from(3));
+
+    let kind_id: u32 = Data.into();
+    assert_eq!(kind_id, 16);
+
+    assert_eq!(Pong.name, "Pong");
+    assert_eq!(Data.name(), "Data");
+
+    const PING_NAME: &str = Ping.meta().name;
+    assert_eq!(PING_NAME, "Ping");
 }